@@ -1,22 +1,74 @@
 use crate::wgpu;
 
+/// Clear a previously-set `Operations` value, enforcing the invariant that a read-only aspect
+/// has no load/store operations at all.
+fn clear_ops<T>(ops: &mut Option<T>) {
+    *ops = None;
+}
+
+/// Translate the builder's sparse, index-preserving attachment slots into the concrete array
+/// `wgpu::RenderPassDescriptor` expects.
+///
+/// This wgpu version's color attachment slots don't have a null representation of their own, so
+/// an unbound (`None`) slot is simply omitted from the resulting array rather than attempting to
+/// construct one - this is the mapping step referred to by the `empty_color_attachment` doc
+/// comment.
+fn resolve_color_attachments<T>(attachments: Vec<Option<T>>) -> Vec<T> {
+    attachments.into_iter().flatten().collect()
+}
+
+/// Whether an attachment's contents should be written back to memory after the render pass, or
+/// discarded.
+///
+/// Discarding is a hint that the attachment's post-pass contents are never read - the classic
+/// case being a multisampled color target that has already been resolved, or a depth/stencil
+/// buffer that's only used within the pass it was written in. On tile-based GPUs (and
+/// increasingly on desktop drivers too) this avoids writing the attachment back to main memory
+/// at all, which is a meaningful bandwidth saving for MSAA-heavy sketches.
+///
+/// This is an internal implementation detail behind the `discard`/`store` builder methods rather
+/// than a public type, since it currently just maps onto `wgpu::Operations::store`; it exists so
+/// that distinction survives a future move to a dedicated discard store op if wgpu ever exposes
+/// one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum StoreOp {
+    /// Write the attachment's contents back to memory once the pass is complete.
+    Store,
+    /// Discard the attachment's contents once the pass is complete.
+    Discard,
+}
+
+impl StoreOp {
+    fn as_bool(self) -> bool {
+        match self {
+            StoreOp::Store => true,
+            StoreOp::Discard => false,
+        }
+    }
+}
+
 /// A builder type to simplify the process of creating a render pass descriptor.
 #[derive(Debug, Default)]
 pub struct Builder<'a> {
-    color_attachments: Vec<wgpu::RenderPassColorAttachmentDescriptor<'a>>,
+    label: Option<&'a str>,
+    color_attachments: Vec<Option<wgpu::RenderPassColorAttachmentDescriptor<'a>>>,
+    color_attachment_labels: Vec<Option<&'a str>>,
     depth_stencil_attachment: Option<wgpu::RenderPassDepthStencilAttachmentDescriptor<'a>>,
+    depth_stencil_attachment_label: Option<&'a str>,
 }
 
 /// A builder type to simplify the process of creating a render pass descriptor.
 #[derive(Debug)]
 pub struct ColorAttachmentDescriptorBuilder<'a> {
     descriptor: wgpu::RenderPassColorAttachmentDescriptor<'a>,
+    label: Option<&'a str>,
 }
 
 /// A builder type to simplify the process of creating a render pass descriptor.
 #[derive(Debug)]
 pub struct DepthStencilAttachmentDescriptorBuilder<'a> {
     descriptor: wgpu::RenderPassDepthStencilAttachmentDescriptor<'a>,
+    label: Option<&'a str>,
 }
 
 impl<'a> ColorAttachmentDescriptorBuilder<'a> {
@@ -33,9 +85,20 @@ impl<'a> ColorAttachmentDescriptorBuilder<'a> {
                 resolve_target: None,
                 ops: Self::DEFAULT_OPS,
             },
+            label: None,
         }
     }
 
+    /// Give this color attachment a debug label.
+    ///
+    /// The label is inserted as a debug marker at the start of the render pass, alongside the
+    /// pass's own label (see `Builder::label`), where graphics debuggers show it, which helps
+    /// distinguish attachments in a multi-target pass.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
     /// Specify the resolve target for this render pass color attachment.
     pub fn resolve_target(mut self, target: Option<&'a wgpu::TextureView>) -> Self {
         self.descriptor.resolve_target = target.map(|t| &**t);
@@ -53,6 +116,30 @@ impl<'a> ColorAttachmentDescriptorBuilder<'a> {
         self.descriptor.ops = ops;
         self
     }
+
+    /// Discard this attachment's contents once the render pass is complete.
+    ///
+    /// See `StoreOp` for when this is worthwhile.
+    pub fn discard(mut self) -> Self {
+        self.descriptor.ops.store = StoreOp::Discard.as_bool();
+        self
+    }
+
+    /// Write this attachment's contents back to memory once the render pass is complete.
+    ///
+    /// This is the default.
+    pub fn store(mut self) -> Self {
+        self.descriptor.ops.store = StoreOp::Store.as_bool();
+        self
+    }
+
+    /// Wire up `target` as the resolve target for this (presumably multisampled) attachment, and
+    /// discard the multisampled attachment's own contents since they're no longer needed once
+    /// they've been resolved into `target`.
+    pub fn msaa_resolve(mut self, target: &'a wgpu::TextureView) -> Self {
+        self.descriptor.resolve_target = Some(&**target);
+        self.discard()
+    }
 }
 
 impl<'a> DepthStencilAttachmentDescriptorBuilder<'a> {
@@ -72,18 +159,89 @@ impl<'a> DepthStencilAttachmentDescriptorBuilder<'a> {
                 depth_ops: Some(Self::DEFAULT_DEPTH_OPS),
                 stencil_ops: Some(Self::DEFAULT_STENCIL_OPS),
             },
+            label: None,
         }
     }
 
+    /// Give this depth/stencil attachment a debug label.
+    ///
+    /// See `ColorAttachmentDescriptorBuilder::label` for why this is useful.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
     /// Define operations for depth pass
     pub fn depth_ops(mut self, ops: wgpu::Operations<f32>) -> Self {
-        self.descriptor.depth_ops = ops;
+        self.descriptor.depth_ops = Some(ops);
         self
     }
 
     /// Define operations for stencil pass
     pub fn stencil_ops(mut self, ops: wgpu::Operations<u32>) -> Self {
-        self.descriptor.stencil_ops = ops;
+        self.descriptor.stencil_ops = Some(ops);
+        self
+    }
+
+    /// Mark the depth aspect of this attachment as read-only.
+    ///
+    /// This allows the same depth texture to be bound as a depth attachment (e.g. for a
+    /// depth-comparison test) and sampled as a regular texture within the same render pass, which
+    /// WebGPU only permits when the attachment declares no load/store operations for that aspect.
+    /// Calling this overwrites any depth operations set via `depth_ops`, as the two are mutually
+    /// exclusive.
+    pub fn depth_read_only(mut self) -> Self {
+        clear_ops(&mut self.descriptor.depth_ops);
+        self
+    }
+
+    /// Mark the stencil aspect of this attachment as read-only.
+    ///
+    /// See `depth_read_only` for details. Calling this overwrites any stencil operations set via
+    /// `stencil_ops`.
+    pub fn stencil_read_only(mut self) -> Self {
+        clear_ops(&mut self.descriptor.stencil_ops);
+        self
+    }
+
+    /// Discard the depth aspect's contents once the render pass is complete.
+    ///
+    /// Has no effect if the depth aspect is read-only (see `depth_read_only`), as there are no
+    /// ops to discard in that case. See `StoreOp` for when discarding is worthwhile.
+    pub fn depth_discard(mut self) -> Self {
+        if let Some(ops) = self.descriptor.depth_ops.as_mut() {
+            ops.store = StoreOp::Discard.as_bool();
+        }
+        self
+    }
+
+    /// Write the depth aspect's contents back to memory once the render pass is complete.
+    ///
+    /// This is the default. Has no effect if the depth aspect is read-only.
+    pub fn depth_store(mut self) -> Self {
+        if let Some(ops) = self.descriptor.depth_ops.as_mut() {
+            ops.store = StoreOp::Store.as_bool();
+        }
+        self
+    }
+
+    /// Discard the stencil aspect's contents once the render pass is complete.
+    ///
+    /// Has no effect if the stencil aspect is read-only (see `stencil_read_only`).
+    pub fn stencil_discard(mut self) -> Self {
+        if let Some(ops) = self.descriptor.stencil_ops.as_mut() {
+            ops.store = StoreOp::Discard.as_bool();
+        }
+        self
+    }
+
+    /// Write the stencil aspect's contents back to memory once the render pass is complete.
+    ///
+    /// This is the default. Has no effect if the stencil aspect is read-only.
+    pub fn stencil_store(mut self) -> Self {
+        if let Some(ops) = self.descriptor.stencil_ops.as_mut() {
+            ops.store = StoreOp::Store.as_bool();
+        }
         self
     }
 }
@@ -101,6 +259,16 @@ impl<'a> Builder<'a> {
         Self::default()
     }
 
+    /// Give this render pass a debug label.
+    ///
+    /// The label is inserted as a debug marker (`pass: <label>`) at the start of the render pass,
+    /// where graphics debuggers (RenderDoc, Xcode GPU capture) show it, making it much easier to
+    /// tell which of several passes in a sketch is which.
+    pub fn label(mut self, label: &'a str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
     /// Add a single color attachment descriptor to the render pass descriptor.
     ///
     /// Call this multiple times in succession to add multiple color attachments.
@@ -112,9 +280,35 @@ impl<'a> Builder<'a> {
     where
         F: FnOnce(ColorAttachmentDescriptorBuilder<'a>) -> ColorAttachmentDescriptorBuilder<'a>,
     {
+        assert!(
+            self.color_attachments.iter().all(Option::is_some),
+            "color_attachment called after empty_color_attachment: this wgpu version has no \
+             null-attachment representation, so a bound attachment after an unbound one would \
+             silently shift down to fill the earlier slot's location index instead of keeping \
+             its own",
+        );
         let builder = ColorAttachmentDescriptorBuilder::new(attachment);
-        let descriptor = color_builder(builder).descriptor;
-        self.color_attachments.push(descriptor);
+        let built = color_builder(builder);
+        self.color_attachment_labels.push(built.label);
+        self.color_attachments.push(Some(built.descriptor));
+        self
+    }
+
+    /// Add an unbound color attachment slot after the bound ones.
+    ///
+    /// Only trailing slots can be left unbound: a bound slot cannot follow an unbound one, so a
+    /// pass whose fragment shader writes to e.g. `@location(2)` but not
+    /// `@location(0)`/`@location(1)` still has to bind attachments to those lower slots.
+    ///
+    /// This wgpu version's `RenderPassDescriptor` has no null-attachment representation of its
+    /// own, so unbound slots are dropped from the array `begin` passes to wgpu (see
+    /// `resolve_color_attachments`) rather than encoded as nulls. That's only safe for trailing
+    /// slots, since dropping one would otherwise shift every attachment after it down to the
+    /// wrong location index - so only call this *after* every `color_attachment` it's meant to
+    /// leave a gap before; calling `color_attachment` again afterwards panics.
+    pub fn empty_color_attachment(mut self) -> Self {
+        self.color_attachment_labels.push(None);
+        self.color_attachments.push(None);
         self
     }
 
@@ -133,12 +327,17 @@ impl<'a> Builder<'a> {
         ) -> DepthStencilAttachmentDescriptorBuilder<'a>,
     {
         let builder = DepthStencilAttachmentDescriptorBuilder::new(attachment);
-        let descriptor = depth_stencil_builder(builder).descriptor;
-        self.depth_stencil_attachment = Some(descriptor);
+        let built = depth_stencil_builder(builder);
+        self.depth_stencil_attachment_label = built.label;
+        self.depth_stencil_attachment = Some(built.descriptor);
         self
     }
 
     /// Return the built color and depth attachments.
+    ///
+    /// Color attachment slots left unbound via `empty_color_attachment` are omitted here (see its
+    /// doc comment), so this keeps its original return type rather than leaking the builder's
+    /// internal `Option`-wrapped representation to callers.
     pub fn into_inner(
         self,
     ) -> (
@@ -148,17 +347,73 @@ impl<'a> Builder<'a> {
         let Builder {
             color_attachments,
             depth_stencil_attachment,
+            ..
         } = self;
-        (color_attachments, depth_stencil_attachment)
+        (resolve_color_attachments(color_attachments), depth_stencil_attachment)
     }
 
     /// Begin a render pass with the specified parameters on the given encoder.
     pub fn begin(self, encoder: &'a mut wgpu::CommandEncoder) -> wgpu::RenderPass<'a> {
+        let label = self.label;
+        let color_attachment_labels = self.color_attachment_labels.clone();
+        // `resolve_color_attachments` compacts out unbound slots, so track which labels survive
+        // that compaction (and at what resulting index) alongside it, rather than indexing the
+        // markers below off the pre-compaction slot position.
+        let color_attachment_present: Vec<bool> =
+            self.color_attachments.iter().map(Option::is_some).collect();
+        let depth_stencil_attachment_label = self.depth_stencil_attachment_label;
         let (color_attachments, depth_stencil_attachment) = self.into_inner();
+        // This wgpu version's `RenderPassDescriptor` has no `label` field, so the pass's own
+        // label is surfaced as a debug marker below, the same way per-attachment labels are.
         let descriptor = wgpu::RenderPassDescriptor {
             color_attachments: &color_attachments,
             depth_stencil_attachment,
         };
-        encoder.begin_render_pass(&descriptor)
+        let mut pass = encoder.begin_render_pass(&descriptor);
+
+        if let Some(label) = label {
+            pass.insert_debug_marker(&format!("pass: {}", label));
+        }
+        // WebGPU has no dedicated per-attachment label field, so surface attachment names as
+        // debug markers within the pass instead - still visible to tools like RenderDoc.
+        let mut resolved_index = 0;
+        for (present, attachment_label) in color_attachment_present
+            .into_iter()
+            .zip(color_attachment_labels)
+        {
+            if !present {
+                continue;
+            }
+            if let Some(attachment_label) = attachment_label {
+                pass.insert_debug_marker(&format!(
+                    "color attachment {}: {}",
+                    resolved_index, attachment_label
+                ));
+            }
+            resolved_index += 1;
+        }
+        if let Some(attachment_label) = depth_stencil_attachment_label {
+            pass.insert_debug_marker(&format!("depth/stencil attachment: {}", attachment_label));
+        }
+
+        pass
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_ops_discards_existing_value() {
+        let mut ops = Some(DepthStencilAttachmentDescriptorBuilder::DEFAULT_DEPTH_OPS);
+        clear_ops(&mut ops);
+        assert!(ops.is_none());
+    }
+
+    #[test]
+    fn resolve_color_attachments_drops_trailing_unbound_slots() {
+        let attachments = vec![Some(0), Some(1), None, None];
+        assert_eq!(resolve_color_attachments(attachments), vec![0, 1]);
     }
 }